@@ -8,26 +8,359 @@ fn print_usage(program: &str, opts: &Options) {
     print!("{}", opts.usage(&brief));
 }
 
-fn save_screenshot(path: &Path, select: bool) -> Result<(), String> {
-    let mut maim = Command::new("maim");
-    if select {
-        maim.arg("-s");
-    }
-    maim.arg(path);
-    let status = match maim.status() {
-        Ok(status) => status,
-        Err(e) => return Err(e.to_string()),
-    };
-    if !status.success() {
-        return Err(format!("maim failed. Exit status: {}", status));
+/// An X selection (or Wayland's analogous primary/regular clipboard) to copy
+/// into or clear.
+#[derive(Clone, Copy)]
+enum Selection {
+    Primary,
+    Secondary,
+    Clipboard,
+}
+
+impl Selection {
+    fn parse(s: &str) -> Result<Selection, String> {
+        match s {
+            "primary" => Ok(Selection::Primary),
+            "secondary" => Ok(Selection::Secondary),
+            "clipboard" => Ok(Selection::Clipboard),
+            other => Err(format!(
+                "Unknown selection {:?}. Expected primary, secondary, or clipboard",
+                other
+            )),
+        }
+    }
+
+    fn as_xclip_arg(&self) -> &'static str {
+        match self {
+            Selection::Primary => "primary",
+            Selection::Secondary => "secondary",
+            Selection::Clipboard => "clipboard",
+        }
+    }
+
+    fn as_xsel_arg(&self) -> &'static str {
+        match self {
+            Selection::Primary => "--primary",
+            Selection::Secondary => "--secondary",
+            Selection::Clipboard => "--clipboard",
+        }
+    }
+}
+
+/// An X11 screenshot tool that `Backend::X11` can drive.
+#[derive(Clone, Copy)]
+enum CaptureTool {
+    Maim,
+    Scrot,
+    Flameshot,
+}
+
+impl CaptureTool {
+    fn parse(s: &str) -> Result<CaptureTool, String> {
+        match s {
+            "maim" => Ok(CaptureTool::Maim),
+            "scrot" => Ok(CaptureTool::Scrot),
+            "flameshot" => Ok(CaptureTool::Flameshot),
+            other => Err(format!(
+                "Unknown capture tool {:?}. Expected maim, scrot, or flameshot",
+                other
+            )),
+        }
+    }
+
+    fn binary_name(&self) -> &'static str {
+        match self {
+            CaptureTool::Maim => "maim",
+            CaptureTool::Scrot => "scrot",
+            CaptureTool::Flameshot => "flameshot",
+        }
+    }
+
+    /// Picks the first of maim, scrot, or flameshot found on `PATH`.
+    fn detect() -> Result<CaptureTool, String> {
+        for tool in [CaptureTool::Maim, CaptureTool::Scrot, CaptureTool::Flameshot] {
+            if command_exists(tool.binary_name()) {
+                return Ok(tool);
+            }
+        }
+        Err("No capture tool found on PATH. Tried: maim, scrot, flameshot".into())
+    }
+}
+
+/// Which display server stack to drive for capture and clipboard access.
+enum Backend {
+    X11 { capture_tool: CaptureTool },
+    Wayland,
+}
+
+impl Backend {
+    /// Picks a backend based on `XDG_SESSION_TYPE`, falling back to X11 if
+    /// the variable is unset or holds an unrecognized value. `capture_tool`
+    /// is X11-only, so it is an error to pass one on a Wayland session
+    /// rather than silently ignoring it.
+    fn detect(capture_tool: Option<CaptureTool>) -> Result<Backend, String> {
+        if is_wayland_session() {
+            if capture_tool.is_some() {
+                return Err(
+                    "--capture-tool is X11-only and has no effect on a Wayland session".into(),
+                );
+            }
+            return Ok(Backend::Wayland);
+        }
+        let capture_tool = match capture_tool {
+            Some(tool) => tool,
+            None => CaptureTool::detect()?,
+        };
+        Ok(Backend::X11 { capture_tool })
+    }
+
+    fn capture(&self, path: &Path, select: bool) -> Result<(), String> {
+        match self {
+            Backend::X11 { capture_tool } => {
+                if !command_exists(capture_tool.binary_name()) {
+                    return Err(format!(
+                        "{} is not installed (not found on PATH)",
+                        capture_tool.binary_name()
+                    ));
+                }
+                match capture_tool {
+                    CaptureTool::Maim => {
+                        let mut maim = Command::new("maim");
+                        if select {
+                            maim.arg("-s");
+                        }
+                        maim.arg(path);
+                        let status = match maim.status() {
+                            Ok(status) => status,
+                            Err(e) => return Err(format!("Failed to run maim: {}", e)),
+                        };
+                        if !status.success() {
+                            return Err(format!("maim failed. Exit status: {}", status));
+                        }
+                        Ok(())
+                    }
+                    CaptureTool::Scrot => {
+                        let mut scrot = Command::new("scrot");
+                        if select {
+                            scrot.arg("-s");
+                        }
+                        scrot.arg(path);
+                        let status = match scrot.status() {
+                            Ok(status) => status,
+                            Err(e) => return Err(format!("Failed to run scrot: {}", e)),
+                        };
+                        if !status.success() {
+                            return Err(format!("scrot failed. Exit status: {}", status));
+                        }
+                        Ok(())
+                    }
+                    CaptureTool::Flameshot => {
+                        let mut flameshot = Command::new("flameshot");
+                        flameshot.arg(if select { "gui" } else { "full" });
+                        flameshot.arg("--raw");
+                        let output = match flameshot.output() {
+                            Ok(output) => output,
+                            Err(e) => return Err(format!("Failed to run flameshot: {}", e)),
+                        };
+                        if !output.status.success() {
+                            return Err(format!(
+                                "flameshot failed. Exit status: {}",
+                                output.status
+                            ));
+                        }
+                        std::fs::write(path, &output.stdout).map_err(|e| e.to_string())
+                    }
+                }
+            }
+            Backend::Wayland => {
+                let mut grim = Command::new("grim");
+                if select {
+                    let slurp = match Command::new("slurp").output() {
+                        Ok(output) => output,
+                        Err(e) => return Err(format!("Failed to run slurp: {}", e)),
+                    };
+                    if !slurp.status.success() {
+                        return Err(format!("slurp failed. Exit status: {}", slurp.status));
+                    }
+                    let geometry = String::from_utf8_lossy(&slurp.stdout);
+                    grim.arg("-g").arg(geometry.trim());
+                }
+                grim.arg(path);
+                let status = match grim.status() {
+                    Ok(status) => status,
+                    Err(e) => return Err(format!("Failed to run grim: {}", e)),
+                };
+                if !status.success() {
+                    return Err(format!("grim failed. Exit status: {}", status));
+                }
+                Ok(())
+            }
+        }
     }
-    Ok(())
+
+    fn copy(&self, data: &[u8], mime: &str, selection: Selection) -> Result<(), String> {
+        use std::io::Write;
+
+        match self {
+            Backend::X11 { .. } => copy_to_clipboard(data, mime, selection),
+            Backend::Wayland => {
+                if let Selection::Secondary = selection {
+                    return Err(
+                        "wl-copy has no secondary selection; use primary or clipboard on Wayland"
+                            .into(),
+                    );
+                }
+                let mut wl_copy = Command::new("wl-copy");
+                wl_copy.arg("--type").arg(mime);
+                if let Selection::Primary = selection {
+                    wl_copy.arg("--primary");
+                }
+                let mut wl_copy = match wl_copy.stdin(Stdio::piped()).spawn() {
+                    Ok(child) => child,
+                    Err(e) => return Err(format!("Failed to run wl-copy: {}", e)),
+                };
+                {
+                    let stdin = match wl_copy.stdin {
+                        Some(ref mut stdin) => stdin,
+                        None => return Err("Child had no stdin".into()),
+                    };
+                    if let Err(e) = stdin.write_all(data) {
+                        return Err(e.to_string());
+                    }
+                }
+                match wl_copy.wait() {
+                    Ok(status) => {
+                        if !status.success() {
+                            Err(format!("wl-copy failed. Exit status: {}", status))
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Spawns a detached copy of this binary that sleeps for `delay` and
+    /// then clears `selection`, so it keeps running after rscrot itself
+    /// exits. Re-executing ourselves (rather than shelling out to `sleep`)
+    /// keeps millisecond-precision delays working on `sleep` implementations
+    /// (e.g. busybox/Termux) that only accept whole-second arguments, and
+    /// lets the clear go through the same tool the copy used: wl-copy on a
+    /// Wayland session, otherwise the xclip/xsel/termux fallback chain of
+    /// `copy_to_clipboard`, rather than assuming xclip is present.
+    fn schedule_clear(&self, delay: std::time::Duration, selection: Selection) -> Result<(), String> {
+        let exe = env::current_exe().map_err(|e| e.to_string())?;
+        Command::new(exe)
+            .arg(INTERNAL_CLEAR_FLAG)
+            .arg(delay.as_millis().to_string())
+            .arg(selection.as_xclip_arg())
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to schedule clipboard clear: {}", e))
+    }
+}
+
+/// Argv[1] used to recognize the hidden "sleep then clear" helper mode that
+/// `Backend::schedule_clear` spawns as a detached child process.
+const INTERNAL_CLEAR_FLAG: &str = "--internal-clear-after";
+
+fn is_wayland_session() -> bool {
+    matches!(env::var("XDG_SESSION_TYPE"), Ok(t) if t.eq_ignore_ascii_case("wayland"))
+}
+
+/// Clears `selection` using whichever of xclip/xsel/termux-clipboard-set is
+/// available, mirroring the fallback chain `copy_to_clipboard` uses.
+fn clear_clipboard_x11(selection: Selection) -> Result<(), String> {
+    if command_exists("xclip") {
+        let status = Command::new("xclip")
+            .arg("-selection")
+            .arg(selection.as_xclip_arg())
+            .arg("-i")
+            .arg("/dev/null")
+            .status()
+            .map_err(|e| format!("Failed to run xclip: {}", e))?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(format!("xclip failed. Exit status: {}", status))
+        };
+    }
+    if command_exists("xsel") {
+        let status = Command::new("xsel")
+            .arg(selection.as_xsel_arg())
+            .arg("--clear")
+            .status()
+            .map_err(|e| format!("Failed to run xsel: {}", e))?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(format!("xsel failed. Exit status: {}", status))
+        };
+    }
+    if command_exists("termux-clipboard-set") {
+        let status = Command::new("termux-clipboard-set")
+            .stdin(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to run termux-clipboard-set: {}", e))?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "termux-clipboard-set failed. Exit status: {}",
+                status
+            ))
+        };
+    }
+    Err("No clipboard tool found to clear. Tried: xclip, xsel, termux-clipboard-set".into())
+}
+
+/// Clears `selection` via wl-copy. wl-copy has no secondary selection.
+fn clear_clipboard_wayland(selection: Selection) -> Result<(), String> {
+    if let Selection::Secondary = selection {
+        return Err("wl-copy has no secondary selection to clear".into());
+    }
+    let mut wl_copy = Command::new("wl-copy");
+    wl_copy.arg("--clear");
+    if let Selection::Primary = selection {
+        wl_copy.arg("--primary");
+    }
+    let status = wl_copy
+        .status()
+        .map_err(|e| format!("Failed to run wl-copy: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("wl-copy failed. Exit status: {}", status))
+    }
+}
+
+/// Parses durations like `500ms`, `30s`, or `5m` into milliseconds.
+fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let (amount, unit_ms) = if let Some(stripped) = s.strip_suffix("ms") {
+        (stripped, 1)
+    } else if let Some(stripped) = s.strip_suffix('s') {
+        (stripped, 1000)
+    } else if let Some(stripped) = s.strip_suffix('m') {
+        (stripped, 60_000)
+    } else {
+        return Err(format!(
+            "Unrecognized duration {:?}. Expected a number followed by ms, s, or m",
+            s
+        ));
+    };
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid duration value: {:?}", s))?;
+    Ok(std::time::Duration::from_millis(amount * unit_ms))
 }
 
 enum Choice {
     SaveAs(PathBuf),
     OpenWith(String),
     CopyToClipboard,
+    UploadImgur,
 }
 
 fn get_save_filename_from_zenity() -> Result<PathBuf, String> {
@@ -53,8 +386,9 @@ fn get_user_choice_from_menu(viewers: &[String]) -> Result<Choice, String> {
         .arg("Action");
     zenity.arg("Copy to clipboard");
     zenity.arg("Save as...");
+    zenity.arg("Upload to imgur");
     for viewer in viewers {
-        zenity.arg(&format!("Open with {}", viewer));
+        zenity.arg(format!("Open with {}", viewer));
     }
     let output = match zenity.output() {
         Ok(output) => output,
@@ -66,6 +400,7 @@ fn get_user_choice_from_menu(viewers: &[String]) -> Result<Choice, String> {
     match &output.stdout[..] {
         b"Save as...\n" => Ok(Choice::SaveAs(get_save_filename_from_zenity()?)),
         b"Copy to clipboard\n" => Ok(Choice::CopyToClipboard),
+        b"Upload to imgur\n" => Ok(Choice::UploadImgur),
         other => {
             for viewer in viewers {
                 if other == format!("Open with {}\n", viewer).as_bytes() {
@@ -89,22 +424,29 @@ fn open_with(viewer: String, path: &Path) -> Result<(), String> {
     }
 }
 
-fn copy_to_clipboard(data: &[u8], target: &str) -> Result<(), String> {
+/// Checks whether `name` is available on `PATH`.
+fn command_exists(name: &str) -> bool {
+    Command::new("which")
+        .arg(name)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Pipes `data` into `cmd`'s stdin and waits for it to finish, labeling
+/// errors with `name` for the benefit of the fallback chain in
+/// `copy_to_clipboard`.
+fn pipe_to_clipboard_command(cmd: &mut Command, data: &[u8], name: &str) -> Result<(), String> {
     use std::io::Write;
 
-    let mut xclip = match Command::new("xclip")
-        .arg("-selection")
-        .arg("clipboard")
-        .arg("-target")
-        .arg(target)
-        .stdin(Stdio::piped())
-        .spawn()
-    {
+    let mut child = match cmd.stdin(Stdio::piped()).spawn() {
         Ok(child) => child,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(format!("Failed to run {}: {}", name, e)),
     };
     {
-        let stdin = match xclip.stdin {
+        let stdin = match child.stdin {
             Some(ref mut stdin) => stdin,
             None => return Err("Child had no stdin".into()),
         };
@@ -112,10 +454,10 @@ fn copy_to_clipboard(data: &[u8], target: &str) -> Result<(), String> {
             return Err(e.to_string());
         }
     }
-    match xclip.wait() {
+    match child.wait() {
         Ok(status) => {
             if !status.success() {
-                Err(format!("xclip failed. Exit status: {}", status))
+                Err(format!("{} failed. Exit status: {}", name, status))
             } else {
                 Ok(())
             }
@@ -124,7 +466,81 @@ fn copy_to_clipboard(data: &[u8], target: &str) -> Result<(), String> {
     }
 }
 
+/// Tries `xclip`, then `xsel`, then `termux-clipboard-set`, using whichever
+/// is first found on `PATH`. `target`/mime is only meaningful to xclip; the
+/// other backends are text-only.
+fn copy_to_clipboard(data: &[u8], target: &str, selection: Selection) -> Result<(), String> {
+    if command_exists("xclip") {
+        return pipe_to_clipboard_command(
+            Command::new("xclip")
+                .arg("-selection")
+                .arg(selection.as_xclip_arg())
+                .arg("-target")
+                .arg(target),
+            data,
+            "xclip",
+        );
+    }
+    if command_exists("xsel") {
+        return pipe_to_clipboard_command(
+            Command::new("xsel").arg(selection.as_xsel_arg()).arg("--input"),
+            data,
+            "xsel",
+        );
+    }
+    if command_exists("termux-clipboard-set") {
+        return pipe_to_clipboard_command(
+            &mut Command::new("termux-clipboard-set"),
+            data,
+            "termux-clipboard-set",
+        );
+    }
+    Err("No clipboard tool found. Tried: xclip, xsel, termux-clipboard-set".into())
+}
+
+/// Uploads `data` anonymously to imgur and returns the resulting image URL.
+fn upload_to_imgur(data: &[u8], client_id: &str) -> Result<String, String> {
+    use base64::Engine as _;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    let response = ureq::post("https://api.imgur.com/3/image")
+        .set("Authorization", &format!("Client-ID {}", client_id))
+        .send_form(&[("image", &encoded), ("type", "base64")]);
+    let body = match response {
+        Ok(resp) => resp.into_string().map_err(|e| e.to_string())?,
+        Err(e) => return Err(format!("imgur upload failed: {}", e)),
+    };
+    extract_imgur_link(&body)
+        .ok_or_else(|| format!("Could not find image link in imgur response: {}", body))
+}
+
+/// Pulls the `data.link` field out of an imgur API response without pulling
+/// in a full JSON parser for a single string.
+fn extract_imgur_link(json: &str) -> Option<String> {
+    let key = "\"link\":\"";
+    let start = json.find(key)? + key.len();
+    let end = json[start..].find('"')? + start;
+    Some(json[start..end].replace("\\/", "/"))
+}
+
 fn main() {
+    let raw_args: Vec<String> = env::args().collect();
+    if raw_args.get(1).map(String::as_str) == Some(INTERNAL_CLEAR_FLAG) {
+        let millis: u64 = raw_args[2]
+            .parse()
+            .expect("invalid delay for internal clipboard clear");
+        let selection =
+            Selection::parse(&raw_args[3]).expect("invalid selection for internal clipboard clear");
+        std::thread::sleep(std::time::Duration::from_millis(millis));
+        let result = if is_wayland_session() {
+            clear_clipboard_wayland(selection)
+        } else {
+            clear_clipboard_x11(selection)
+        };
+        result.unwrap();
+        return;
+    }
+
     let mut args = env::args();
     let program = args.next().unwrap();
 
@@ -142,6 +558,30 @@ fn main() {
         "Sleep n seconds before taking the screenshot",
         "SECONDS",
     );
+    opts.optopt(
+        "",
+        "imgur-client-id",
+        "Client ID to use for anonymous imgur uploads (overrides IMGUR_CLIENT_ID)",
+        "CLIENT_ID",
+    );
+    opts.optopt(
+        "",
+        "selection",
+        "Clipboard selection to copy to: primary, secondary, or clipboard (default clipboard)",
+        "SELECTION",
+    );
+    opts.optopt(
+        "",
+        "clear-after",
+        "Clear the clipboard after a duration, e.g. 500ms, 30s, 5m",
+        "DURATION",
+    );
+    opts.optopt(
+        "",
+        "capture-tool",
+        "X11 capture tool to use: maim, scrot, or flameshot (auto-detected if unspecified)",
+        "TOOL",
+    );
     opts.optflag("h", "help", "print this help menu");
     let matches = match opts.parse(args) {
         Ok(m) => m,
@@ -154,13 +594,30 @@ fn main() {
     let viewers = matches.opt_strs("viewer");
     let file_path = env::temp_dir().join("rscrot_screenshot.png");
     let select = matches.opt_present("s");
+    let selection = match matches.opt_str("selection") {
+        Some(s) => Selection::parse(&s).unwrap(),
+        None => Selection::Clipboard,
+    };
+    let clear_after = matches
+        .opt_str("clear-after")
+        .map(|d| parse_duration(&d).unwrap());
     if let Some(sleep_timer) = matches.opt_str("timer") {
         let seconds = sleep_timer
             .parse()
             .expect("Timer value needs to be numeric");
         std::thread::sleep(std::time::Duration::from_secs(seconds));
     }
-    save_screenshot(&file_path, select).unwrap();
+    let capture_tool = matches
+        .opt_str("capture-tool")
+        .map(|t| CaptureTool::parse(&t).unwrap());
+    let backend = Backend::detect(capture_tool).unwrap();
+    backend.capture(&file_path, select).unwrap();
+    let copy_and_maybe_clear = |backend: &Backend, data: &[u8], mime: &str| {
+        backend.copy(data, mime, selection).unwrap();
+        if let Some(delay) = clear_after {
+            backend.schedule_clear(delay, selection).unwrap();
+        }
+    };
     match get_user_choice_from_menu(&viewers).unwrap() {
         Choice::SaveAs(path) => {
             std::fs::copy(&file_path, path.to_str().unwrap().trim())
@@ -169,7 +626,17 @@ fn main() {
         Choice::OpenWith(viewer) => open_with(viewer, &file_path).unwrap(),
         Choice::CopyToClipboard => {
             let image = std::fs::read(file_path).unwrap();
-            copy_to_clipboard(&image, "image/png").unwrap();
+            copy_and_maybe_clear(&backend, &image, "image/png");
+        }
+        Choice::UploadImgur => {
+            let client_id = matches
+                .opt_str("imgur-client-id")
+                .or_else(|| env::var("IMGUR_CLIENT_ID").ok())
+                .expect("No imgur client ID given (use --imgur-client-id or IMGUR_CLIENT_ID)");
+            let image = std::fs::read(file_path).unwrap();
+            let url = upload_to_imgur(&image, &client_id).unwrap();
+            println!("{}", url);
+            copy_and_maybe_clear(&backend, url.as_bytes(), "text/plain");
         }
     }
 }